@@ -1,12 +1,17 @@
+use pyo3::exceptions::PyRuntimeError;
 use pyo3::prelude::*;
 use external_sort::{ExternalSorter, ExternallySortable};
+use rayon::prelude::*;
+use rayon::ThreadPoolBuilder;
 use std::cmp::Ordering::{self, Equal, Greater, Less};
 use std::fs::{File, OpenOptions};
-use std::io::{BufRead, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
 use std::iter::Peekable;
-use std::path::PathBuf;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
 use std::str::Chars;
 use serde::{Deserialize, Serialize};
+use tempfile::NamedTempFile;
 
 
 // Define a string structure that can be sorted externally
@@ -37,16 +42,167 @@ fn linecomp(l1: &str, l2: &str) -> i8 {
     }
 }
 
+/// Python-exposed wrapper around [`tsv_cmp_debug`].
+///
+/// Returns `(ordering, l1_range, l2_range)`, where `ordering` is `-1`, `0` or `1` like
+/// [`linecomp`] and `l1_range`/`l2_range` are `(start, end)` byte offset pairs into `l1` and `l2`
+/// identifying the span that was actually consulted before the ordering was determined.
+#[pyfunction]
+fn linecomp_debug(l1: &str, l2: &str) -> (i8, (usize, usize), (usize, usize)) {
+    let (ordering, l1_range, l2_range) = tsv_cmp_debug(l1, l2);
+    let ordering = match ordering {
+        Less => -1,
+        Equal => 0,
+        Greater => 1,
+    };
+    (ordering, (l1_range.start, l1_range.end), (l2_range.start, l2_range.end))
+}
+
+/// Sort `lines` in place and return them.
+///
+/// `natural`, `general_numeric` and `nulls_first` pick the comparator, exactly as in
+/// [`sort_file_lines`].
 #[pyfunction]
-fn sort_lines(lines: Vec<String>) -> Vec<String> {
+fn sort_lines(
+    lines: Vec<String>,
+    natural: bool,
+    general_numeric: bool,
+    nulls_first: Option<bool>,
+) -> Vec<String> {
     let mut lines = lines;
-    lines.sort_by(|a, b| tsv_cmp(a, b));
+    let cmp = select_comparator(natural, general_numeric, nulls_first);
+    lines.sort_by(|a, b| cmp(a, b));
     lines
 }
 
+/// Pick the line comparator for `sort_lines`/`sort_file_lines`: [`tsv_cmp_general`] when
+/// `general_numeric` is `true`, else [`tsv_cmp_natural`] when `natural` is `true`, else
+/// [`tsv_cmp`]. `general_numeric` takes precedence over `natural` when both are set. When
+/// `nulls_first` is `Some`, `\N` NULL fields are additionally sorted before (`Some(true)`) or
+/// after (`Some(false)`) every real value, per [`tsv_cmp_nulls`].
+fn select_comparator(
+    natural: bool,
+    general_numeric: bool,
+    nulls_first: Option<bool>,
+) -> fn(&str, &str) -> Ordering {
+    match (general_numeric, natural, nulls_first) {
+        (true, _, None) => tsv_cmp_general,
+        (true, _, Some(true)) => tsv_cmp_general_nulls_first,
+        (true, _, Some(false)) => tsv_cmp_general_nulls_last,
+        (false, true, None) => tsv_cmp_natural,
+        (false, true, Some(true)) => tsv_cmp_natural_nulls_first,
+        (false, true, Some(false)) => tsv_cmp_natural_nulls_last,
+        (false, false, None) => tsv_cmp,
+        (false, false, Some(true)) => tsv_cmp_nulls_first,
+        (false, false, Some(false)) => tsv_cmp_nulls_last,
+    }
+}
+
+/// Drop adjacent duplicate rows from an already-sorted list of lines.
+///
+/// `lines` must already be sorted with the same `natural`, `general_numeric` and `nulls_first`
+/// comparator, e.g. by `sort_lines`. Two lines are considered duplicates, and the later one is
+/// dropped, when that comparator finds them `Equal` restricted to the columns in `key_fields` (an
+/// empty `key_fields` compares whole lines).
+///
+/// # Returns
+///
+/// The deduplicated lines, together with the number of duplicates removed.
+#[pyfunction]
+fn dedup_lines(
+    lines: Vec<String>,
+    key_fields: Vec<usize>,
+    natural: bool,
+    general_numeric: bool,
+    nulls_first: Option<bool>,
+) -> (Vec<String>, usize) {
+    let cmp = select_comparator(natural, general_numeric, nulls_first);
+    let mut deduped: Vec<String> = Vec::with_capacity(lines.len());
+    let mut duplicates_removed = 0;
+    for line in lines {
+        let is_duplicate = deduped
+            .last()
+            .map(|prev| rows_are_duplicates(prev, &line, &key_fields, cmp))
+            .unwrap_or(false);
+        if is_duplicate {
+            duplicates_removed += 1;
+        } else {
+            deduped.push(line);
+        }
+    }
+    (deduped, duplicates_removed)
+}
+
+/// Decide whether `prev` and `current` are duplicates of each other for dedup purposes.
+///
+/// With an empty `key_fields`, whole lines are compared with `cmp`. Otherwise only the
+/// tab-separated columns named by `key_fields` are compared, in the given order. `cmp` must be
+/// the same comparator the caller sorted with, so that rows considered equal by the sort are also
+/// considered equal here.
+fn rows_are_duplicates(
+    prev: &str,
+    current: &str,
+    key_fields: &[usize],
+    cmp: fn(&str, &str) -> Ordering,
+) -> bool {
+    if key_fields.is_empty() {
+        cmp(prev, current) == Equal
+    } else {
+        cmp(&dedup_key(prev, key_fields), &dedup_key(current, key_fields)) == Equal
+    }
+}
+
+/// Build the comparison key used by `rows_are_duplicates` out of the given tab-columns of `line`.
+fn dedup_key(line: &str, key_fields: &[usize]) -> String {
+    let fields: Vec<&str> = line.split('\t').collect();
+    key_fields
+        .iter()
+        .map(|&i| fields.get(i).copied().unwrap_or(""))
+        .collect::<Vec<&str>>()
+        .join("\t")
+}
+
 // This is the end marker for an SQL COPY stream:
 const SQL_COPY_END: &str = "\\.";
 
+/// Iterator over the records of a `BufRead`, analogous to [`std::io::Lines`] but splitting on a
+/// NUL byte instead of `\n`, so that COPY data containing embedded newlines can still be read one
+/// record at a time.
+struct ZeroTerminatedRecords<B> {
+    buf: B,
+}
+
+impl<B: BufRead> Iterator for ZeroTerminatedRecords<B> {
+    type Item = io::Result<String>;
+
+    fn next(&mut self) -> Option<io::Result<String>> {
+        let mut buf = Vec::new();
+        match self.buf.read_until(0, &mut buf) {
+            Ok(0) => None,
+            Ok(_) => {
+                if buf.last() == Some(&0) {
+                    buf.pop();
+                }
+                Some(String::from_utf8(buf).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err)))
+            }
+            Err(err) => Some(Err(err)),
+        }
+    }
+}
+
+/// Return a boxed iterator over the records of `input`, splitting on `\0` when `zero_terminated`
+/// is `true` and on `\n` otherwise.
+fn record_lines<'a, R: BufRead + 'a>(
+    input: R,
+    zero_terminated: bool,
+) -> Box<dyn Iterator<Item = io::Result<String>> + 'a> {
+    if zero_terminated {
+        Box::new(ZeroTerminatedRecords { buf: input })
+    } else {
+        Box::new(input.lines())
+    }
+}
+
 macro_rules! DIGIT {
     () => {
         Some('0'..='9')
@@ -67,11 +223,31 @@ macro_rules! DIGIT {
 /// * `input` - The input file to read lines from.
 /// * `output` - The output file to write sorted lines to.
 /// * `start` - The start position in the input file.
-/// * `end` - The characters of a line that marks the end of the range.
+/// * `natural` - When `true`, compare fields with [`tsv_cmp_natural`] instead of [`tsv_cmp`], so
+///   embedded digit runs such as `img2` and `img10` sort in natural rather than lexicographic
+///   order.
+/// * `general_numeric` - When `true`, compare fields with [`tsv_cmp_general`] instead, so
+///   scientific notation, explicit `+` signs, and thousands separators sort as real numbers.
+///   Takes precedence over `natural` when both are `true`.
+/// * `dedup` - When `true`, drop a sorted line that compares `Equal` to the line emitted right
+///   before it, instead of writing it out. Equality is decided with whichever comparator
+///   `natural`, `general_numeric` and `nulls_first` select, so adjacency always matches the order
+///   the lines were actually sorted in.
+/// * `key_fields` - The tab-column indices to compare when deduplicating, e.g. `[0, 2]` to
+///   consider two rows duplicates when only their first and third columns are equal. An empty
+///   list compares whole lines. Ignored unless `dedup` is `true`.
+/// * `zero_terminated` - When `true`, records are delimited by a NUL byte instead of `\n`, so
+///   COPY data containing embedded newlines round-trips correctly. The end marker and the sorted
+///   output are both written back with the same terminator.
+/// * `nulls_first` - When `Some(true)`, PostgreSQL's `\N` COPY NULL sentinel sorts before every
+///   real value in a field, per [`tsv_cmp_nulls`]; when `Some(false)`, after every real value.
+///   `None` leaves `\N` to sort as an ordinary string, the pre-existing behavior.
 ///
 /// # Returns
 ///
-/// The function returns the number of lines read and written.
+/// The function returns the stream position in the input file reached once the end marker was
+/// found, together with the number of duplicate lines that `dedup` removed (`0` when `dedup` is
+/// `false`).
 ///
 /// # Errors
 ///
@@ -86,39 +262,143 @@ macro_rules! DIGIT {
 /// let input = "input.txt";
 /// let output = "output.txt";
 /// let start = 0;
-/// let end = "END";
-/// let result = sort_file_lines(input, output, start, end);
+/// let result = sort_file_lines(input, output, start, false, false, false, vec![], false, None);
 /// assert!(result.is_ok());
 /// ```
 ///
 #[pyfunction]
-fn sort_file_lines(input: PathBuf, output: PathBuf, start: u64) -> PyResult<u64> {
+fn sort_file_lines(
+    input: PathBuf,
+    output: PathBuf,
+    start: u64,
+    natural: bool,
+    general_numeric: bool,
+    dedup: bool,
+    key_fields: Vec<usize>,
+    zero_terminated: bool,
+    nulls_first: Option<bool>,
+) -> PyResult<(u64, u64)> {
     // Open the input file and seek to the start position
     let mut input_file = File::open(input)?;
     input_file.seek(SeekFrom::Start(start))?;
     // Wrap the input file in a buffered reader
     let mut input = BufReader::new(&mut input_file);
-    // Create an iterator which reads lines until the end marker and doesn't consume the end marker
-    // See https://stackoverflow.com/questions/39935158 for `.by_ref()` explanation
-    let binding = input.by_ref().lines().peekable();
-    let lines = binding
+    // Create an iterator which reads records until the end marker and doesn't consume the end
+    // marker. See https://stackoverflow.com/questions/39935158 for `.by_ref()` explanation
+    let lines = record_lines(input.by_ref(), zero_terminated)
         .take_while(|line| line.as_ref().map(|l| l != SQL_COPY_END).unwrap_or(false))
         .map(|line| TsvLine::new(&line.unwrap()));
-    // Do the external sort
+    // Do the external sort, picking the comparator based on the `natural`/`general_numeric`/
+    // `nulls_first` flags
+    let cmp = select_comparator(natural, general_numeric, nulls_first);
     let iter = ExternalSorter::new(1000000, None).sort_by(
         lines,
-        |a, b| tsv_cmp(a.the_line.as_str(), b.the_line.as_str()),
+        |a, b| cmp(a.the_line.as_str(), b.the_line.as_str()),
     ).unwrap();
-    // Append the sorted lines to the output file
+    // Append the sorted lines to the output file, dropping duplicates of the previously emitted
+    // line when `dedup` is set
     let output_file = OpenOptions::new().append(true).open(output)?;
     let mut output = BufWriter::new(output_file);
+    let terminator: char = if zero_terminated { '\0' } else { '\n' };
+    let mut previous_line: Option<String> = None;
+    let mut duplicates_removed: u64 = 0;
     for line in iter {
-        writeln!(output, "{}", line.unwrap().the_line)?;
+        let line = line.unwrap().the_line;
+        let is_duplicate = dedup
+            && previous_line
+                .as_deref()
+                .map(|prev| rows_are_duplicates(prev, &line, &key_fields, cmp))
+                .unwrap_or(false);
+        if is_duplicate {
+            duplicates_removed += 1;
+            continue;
+        }
+        write!(output, "{line}{terminator}")?;
+        previous_line = Some(line);
+    }
+    // Write the end marker (which was not consumed by take_while)
+    write!(output, "{SQL_COPY_END}{terminator}")?;
+    // return the stream position from the counting reader object, and the duplicate count
+    Ok((input.stream_position().unwrap(), duplicates_removed))
+}
+
+/// Sort several independent COPY blocks from `input` in parallel and append the results, in
+/// their original order, to `output`.
+///
+/// Each element of `offsets` is the start position of one COPY block in `input`, exactly as
+/// passed to `sort_file_lines`. Since a COPY block's data ends at the `\\.` marker, each block is
+/// an independent sort domain, so the blocks are farmed out across a rayon thread pool: each is
+/// sorted with `tsv_cmp` into its own temporary file, and once all blocks are done, the temporary
+/// files are concatenated onto `output` in the original `offsets` order.
+///
+/// # Arguments
+///
+/// * `input` - The input file to read lines from.
+/// * `output` - The output file to append the sorted blocks to, in order.
+/// * `offsets` - The start position of each COPY block in `input`.
+/// * `threads` - The size of the rayon thread pool to use, or `0` for the rayon default.
+///
+/// # Returns
+///
+/// The function returns, for each block in `offsets` order, the stream position in `input`
+/// reached once its end marker was found, just like `sort_file_lines` does for a single block.
+///
+/// # Errors
+///
+/// The function returns an error if the thread pool cannot be built, any input block cannot be
+/// read, any temporary file cannot be written, or `output` cannot be written.
+#[pyfunction]
+fn sort_file_blocks(
+    input: PathBuf,
+    output: PathBuf,
+    offsets: Vec<u64>,
+    threads: usize,
+) -> PyResult<Vec<u64>> {
+    let pool = ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .build()
+        .map_err(|err| PyRuntimeError::new_err(err.to_string()))?;
+    let blocks: Vec<PyResult<(NamedTempFile, u64)>> =
+        pool.install(|| offsets.par_iter().map(|&start| sort_block_to_temp_file(&input, start)).collect());
+
+    let output_file = OpenOptions::new().append(true).open(output)?;
+    let mut output = BufWriter::new(output_file);
+    let mut end_positions = Vec::with_capacity(blocks.len());
+    for block in blocks {
+        let (temp_file, end_position) = block?;
+        io::copy(&mut temp_file.reopen()?, &mut output)?;
+        end_positions.push(end_position);
+    }
+    Ok(end_positions)
+}
+
+/// Sort a single COPY block, starting at `start` in `input`, into its own temporary file.
+///
+/// Returns the temporary file together with the stream position in `input` reached once the end
+/// marker was found, mirroring the second half of `sort_file_lines`.
+fn sort_block_to_temp_file(input: &Path, start: u64) -> PyResult<(NamedTempFile, u64)> {
+    let mut input_file = File::open(input)?;
+    input_file.seek(SeekFrom::Start(start))?;
+    let mut input = BufReader::new(&mut input_file);
+    let binding = input.by_ref().lines().peekable();
+    let lines = binding
+        .take_while(|line| line.as_ref().map(|l| l != SQL_COPY_END).unwrap_or(false))
+        .map(|line| TsvLine::new(&line.unwrap()));
+    let iter = ExternalSorter::new(1000000, None).sort_by(
+        lines,
+        |a, b| tsv_cmp(a.the_line.as_str(), b.the_line.as_str()),
+    ).unwrap();
+
+    let mut temp_file = NamedTempFile::new()?;
+    {
+        let mut writer = BufWriter::new(temp_file.as_file_mut());
+        for line in iter {
+            writeln!(writer, "{}", line.unwrap().the_line)?;
+        }
+        writeln!(writer, "{SQL_COPY_END}")?;
     }
-    // Write the end marker (which was not consumed by peeking_take_while)
-    writeln!(output, "{SQL_COPY_END}")?;
-    // return the stream position from the counting reader object
-    Ok(input.stream_position().unwrap())
+    let end_position = input.stream_position().unwrap();
+    Ok((temp_file, end_position))
 }
 
 /// A Python module implemented in Rust.
@@ -126,8 +406,11 @@ fn sort_file_lines(input: PathBuf, output: PathBuf, start: u64) -> PyResult<u64>
 #[pyo3(name = "_tsv_sort")]
 fn tsv_sort(_py: Python, m: &PyModule) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(linecomp, m)?)?;
+    m.add_function(wrap_pyfunction!(linecomp_debug, m)?)?;
     m.add_function(wrap_pyfunction!(sort_lines, m)?)?;
+    m.add_function(wrap_pyfunction!(dedup_lines, m)?)?;
     m.add_function(wrap_pyfunction!(sort_file_lines, m)?)?;
+    m.add_function(wrap_pyfunction!(sort_file_blocks, m)?)?;
     Ok(())
 }
 
@@ -268,6 +551,543 @@ fn skip_leading_zeros(field_chars: &mut Peekable<Chars>) {
     }
 }
 
+/// Debug variant of [`tsv_cmp`] that also reports which bytes of `l1` and `l2` were actually
+/// consulted before the ordering was determined.
+///
+/// Because `tsv_cmp` short-circuits field-by-field and digit-by-digit, the decisive span is
+/// usually tiny: one differing digit, the negative-sign position, or similar. Surfacing it lets
+/// callers diagnose why two lines sorted the way they did.
+///
+/// # Returns
+///
+/// A tuple `(ordering, l1_range, l2_range)`, where `l1_range`/`l2_range` are the byte ranges,
+/// counted from the start of each line, that were read before `ordering` could be decided. For
+/// `Equal`, both ranges cover the full length of their line.
+pub fn tsv_cmp_debug(l1: &str, l2: &str) -> (Ordering, Range<usize>, Range<usize>) {
+    let mut l1_chars = l1.chars().peekable();
+    let mut l2_chars = l2.chars().peekable();
+    let mut l1_pos = 0;
+    let mut l2_pos = 0;
+    let mut l1_larger;
+
+    'next_field: loop {
+        // handle negative prefixes and end of lines
+        l1_larger = Greater;  // reset negative prefix status for each new field
+        match (l1_chars.peek().copied(), l2_chars.peek().copied()) {
+            (Some('-'), Some('-')) => {  // both l1 and l2 have negative prefixes
+                l1_larger = Less;  // invert the comparison of absolute values
+                l1_pos += l1_chars.next().unwrap().len_utf8();
+                l2_pos += l2_chars.next().unwrap().len_utf8();
+            }
+            (Some(a @ '-'), Some(b)) => {  // only l1 has a negative prefix, so l1 < l2
+                return (Less, 0..l1_pos + a.len_utf8(), 0..l2_pos + b.len_utf8());
+            }
+            (Some(a), Some(b @ '-')) => {  // only l2 has a negative prefix, so l1 > l2
+                return (Greater, 0..l1_pos + a.len_utf8(), 0..l2_pos + b.len_utf8());
+            }
+            (Some(_), Some(_)) => {}  // neither has a negative prefix, continue
+            (Some(a), None) => return (Greater, 0..l1_pos + a.len_utf8(), 0..l2_pos),  // l2 ended
+            (None, Some(b)) => return (Less, 0..l1_pos, 0..l2_pos + b.len_utf8()),  // l1 ended
+            (None, None) => return (Equal, 0..l1_pos, 0..l2_pos),  // end of both lines
+        }
+
+        skip_leading_zeros_debug(&mut l1_chars, &mut l1_pos);
+        skip_leading_zeros_debug(&mut l2_chars, &mut l2_pos);
+
+        let mut sorting_so_far = Equal;
+        loop {
+            let c1 = l1_chars.next();
+            let c2 = l2_chars.next();
+            if let Some(c) = c1 {
+                l1_pos += c.len_utf8();
+            }
+            if let Some(c) = c2 {
+                l2_pos += c.len_utf8();
+            }
+            match (c1, c2, sorting_so_far) {
+                (c1 @ DIGIT!(), c2 @ DIGIT!(), Equal) => sorting_so_far = c1.cmp(&c2),
+                (_, DIGIT!(), Equal) => return (l1_larger.reverse(), 0..l1_pos, 0..l2_pos),
+                (DIGIT!(), _, Equal) => return (l1_larger, 0..l1_pos, 0..l2_pos),
+                (None | Some('\t' | '.'), None | Some('\t' | '.'), Less) => {
+                    return (l1_larger.reverse(), 0..l1_pos, 0..l2_pos);
+                }
+                (None | Some('\t' | '.'), None | Some('\t' | '.'), Greater) => {
+                    return (l1_larger, 0..l1_pos, 0..l2_pos);
+                }
+                (None, None, Equal) => return (Equal, 0..l1_pos, 0..l2_pos),
+                (Some('\t'), Some('\t'), Equal) => continue 'next_field,
+                (Some('.'), Some('.'), Equal) => break,
+                (Some('.'), Some(_), Equal) => return (l1_larger, 0..l1_pos, 0..l2_pos),
+                (Some(_), Some('.'), Equal) => return (l1_larger.reverse(), 0..l1_pos, 0..l2_pos),
+                (Some(_), None | Some('\t'), _) => return (l1_larger, 0..l1_pos, 0..l2_pos),
+                (None | Some('\t'), Some(_), _) => return (l1_larger.reverse(), 0..l1_pos, 0..l2_pos),
+                (c1 @ Some(_), c2 @ Some(_), Equal) => sorting_so_far = c1.cmp(&c2),
+                (Some(_), Some(_), Less) => return (l1_larger.reverse(), 0..l1_pos, 0..l2_pos),
+                (Some(_), Some(_), Greater) => return (l1_larger, 0..l1_pos, 0..l2_pos),
+            }
+        }
+
+        // l1 and l2 have the same integer part, compare the fractional part
+        loop {
+            let c1 = l1_chars.next();
+            let c2 = l2_chars.next();
+            if let Some(c) = c1 {
+                l1_pos += c.len_utf8();
+            }
+            if let Some(c) = c2 {
+                l2_pos += c.len_utf8();
+            }
+            match (c1, c2) {
+                (Some('\t'), Some('\t')) => continue 'next_field,  // values equal, continue
+                (Some(_), None | Some('\t')) => return (l1_larger, 0..l1_pos, 0..l2_pos),
+                (None | Some('\t'), Some(_)) => return (l1_larger.reverse(), 0..l1_pos, 0..l2_pos),
+                (None, None) => return (Equal, 0..l1_pos, 0..l2_pos),
+                (Some(c1), Some(c2)) => match c1.cmp(&c2) {
+                    Less => return (l1_larger.reverse(), 0..l1_pos, 0..l2_pos),
+                    Greater => return (l1_larger, 0..l1_pos, 0..l2_pos),
+                    Equal => continue,
+                },
+            }
+        }
+    }
+}
+
+/// Like [`skip_leading_zeros`], but also advances a running byte offset for [`tsv_cmp_debug`].
+fn skip_leading_zeros_debug(field_chars: &mut Peekable<Chars>, pos: &mut usize) {
+    while let Some(c) = field_chars.peek().copied() {
+        if c == '0' {
+            field_chars.next();
+            *pos += c.len_utf8();
+        } else {
+            break;
+        }
+    }
+}
+
+/// Natural-order variant of [`tsv_cmp`].
+///
+/// `tsv_cmp` only recognizes a field as numeric when the digits come first; once it meets a
+/// non-digit character it falls back to plain character-by-character comparison for the rest of
+/// the field, so e.g. `file2.txt` sorts after `file10.txt`. `tsv_cmp_natural` instead treats
+/// *every* maximal run of digits anywhere in a field as a number, so `img2` < `img10` and
+/// `v1.9` < `v1.10`, while still comparing non-digit characters directly and honouring the same
+/// tab-field and negative-sign semantics as `tsv_cmp`.
+///
+/// # Arguments
+///
+/// * `l1` - The first line to compare.
+/// * `l2` - The second line to compare.
+///
+/// # Returns
+///
+/// The function returns an `Ordering` value, which is one of `Less`, `Equal`, or `Greater`.
+///
+/// # Examples
+///
+/// ```
+/// use pgtricks::tsv_cmp_natural;
+/// use std::cmp::Ordering;
+///
+/// assert_eq!(tsv_cmp_natural("img2", "img10"), Ordering::Less);
+/// assert_eq!(tsv_cmp_natural("img10", "img2"), Ordering::Greater);
+/// ```
+pub fn tsv_cmp_natural(l1: &str, l2: &str) -> Ordering {
+    let mut l1_chars = l1.chars().peekable();
+    let mut l2_chars = l2.chars().peekable();
+
+    'next_field: loop {
+        // handle negative prefixes and end of lines, same semantics as in `tsv_cmp`
+        let mut l1_larger = Greater;
+        match (l1_chars.peek(), l2_chars.peek()) {
+            (Some('-'), Some('-')) => {  // both l1 and l2 have negative prefixes
+                l1_larger = Less;  // invert the comparison of the rest of the field
+                l1_chars.next();
+                l2_chars.next();
+            }
+            (Some('-'), Some(_)) => return Less,  // only l1 has a negative prefix
+            (Some(_), Some('-')) => return Greater,  // only l2 has a negative prefix
+            (Some(_), Some(_)) => {}  // neither has a negative prefix, continue
+            (Some(_), None) => return Greater,  // end of line for l2, so l1 > l2
+            (None, Some(_)) => return Less,  // end of line for l1, so l1 < l2
+            (None, None) => return Equal,  // end of both lines, so l1 == l2
+        }
+
+        loop {
+            match (l1_chars.peek().copied(), l2_chars.peek().copied()) {
+                // both sides are on a digit: consume and compare the whole run at once
+                (DIGIT!(), DIGIT!()) => match compare_digit_runs(&mut l1_chars, &mut l2_chars) {
+                    Equal => continue,
+                    ordering => return if l1_larger == Less { ordering.reverse() } else { ordering },
+                },
+                (Some('\t'), Some('\t')) => {  // end of field, continue with the next one
+                    l1_chars.next();
+                    l2_chars.next();
+                    continue 'next_field;
+                }
+                (None, None) => return Equal,  // end of both lines, so l1 == l2
+                (Some(_), None | Some('\t')) => return l1_larger,  // l1 field longer
+                (None | Some('\t'), Some(_)) => return l1_larger.reverse(),  // l2 field longer
+                (Some(c1), Some(c2)) => {  // neither side is a digit run boundary, compare directly
+                    l1_chars.next();
+                    l2_chars.next();
+                    match c1.cmp(&c2) {
+                        Equal => continue,
+                        ordering => return if l1_larger == Less { ordering.reverse() } else { ordering },
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Consume a maximal run of digits from each of `l1_chars` and `l2_chars` and compare them as
+/// numbers: first by the number of significant (non-leading-zero) digits, then lexicographically
+/// over those digits, and finally, as a tiebreak, by the number of stripped leading zeros (so
+/// `01` is stable but consistently sorts after `1`).
+fn compare_digit_runs(l1_chars: &mut Peekable<Chars>, l2_chars: &mut Peekable<Chars>) -> Ordering {
+    let l1_zeros = skip_zeros_counting(l1_chars);
+    let l2_zeros = skip_zeros_counting(l2_chars);
+
+    let l1_len = digit_run_len(l1_chars);
+    let l2_len = digit_run_len(l2_chars);
+    match l1_len.cmp(&l2_len) {
+        Equal => {}
+        ordering => return ordering,
+    }
+
+    for _ in 0..l1_len {
+        match l1_chars.next().cmp(&l2_chars.next()) {
+            Equal => continue,
+            ordering => return ordering,
+        }
+    }
+
+    l1_zeros.cmp(&l2_zeros)
+}
+
+/// Skip leading zeros, like `skip_leading_zeros`, but return how many were skipped.
+fn skip_zeros_counting(field_chars: &mut Peekable<Chars>) -> usize {
+    let mut count = 0;
+    while let Some('0') = field_chars.peek() {
+        field_chars.next();
+        count += 1;
+    }
+    count
+}
+
+/// The number of digits remaining in the current run, without consuming them.
+fn digit_run_len(field_chars: &Peekable<Chars>) -> usize {
+    field_chars.clone().take_while(char::is_ascii_digit).count()
+}
+
+/// General-numeric variant of [`tsv_cmp`].
+///
+/// `tsv_cmp` parses a leading sign, digits, and a single decimal point, so scientific notation
+/// (`1.5e10`), explicit `+` signs, and grouped values (`1,000,000`) all fall back to plain
+/// character comparison and sort incorrectly. `tsv_cmp_general` instead parses each field as a
+/// general-numeric token -- an optional sign, digits with optional thousands separators, an
+/// optional fractional part, and an optional `e`/`E` exponent -- and compares two such tokens by
+/// sign, then by effective magnitude, then digit by digit over their significant digits. A field
+/// that isn't a clean general-numeric token is compared with plain old [`tsv_cmp`] instead, so
+/// non-numeric fields keep sorting exactly as they do today.
+///
+/// Like `tsv_cmp`, this isn't a perfect numeric comparison: two equal-magnitude values whose
+/// significands carry different trailing zeros compare unequal, e.g. `1.5e2` (significant digits
+/// `15`) is considered less than `150` (significant digits `150`) even though both equal 150. This
+/// is the same class of imprecision as `tsv_cmp`'s `123.00 > 123.0` (see above), which is fine for
+/// our purposes.
+///
+/// # Examples
+///
+/// ```
+/// use pgtricks::tsv_cmp_general;
+/// use std::cmp::Ordering;
+///
+/// assert_eq!(tsv_cmp_general("1.5e2", "2e1"), Ordering::Greater); // 150 > 20
+/// assert_eq!(tsv_cmp_general("1,000,000", "999999"), Ordering::Greater);
+/// ```
+pub fn tsv_cmp_general(l1: &str, l2: &str) -> Ordering {
+    let mut l1_fields = l1.split('\t');
+    let mut l2_fields = l2.split('\t');
+    loop {
+        match (l1_fields.next(), l2_fields.next()) {
+            (None, None) => return Equal,
+            (Some(_), None) => return Greater,
+            (None, Some(_)) => return Less,
+            (Some(f1), Some(f2)) if f1 == f2 => continue,
+            (Some(f1), Some(f2)) => {
+                match compare_general_numeric_fields(f1, f2).unwrap_or_else(|| tsv_cmp(f1, f2)) {
+                    Equal => continue,
+                    ordering => return ordering,
+                }
+            }
+        }
+    }
+}
+
+/// A field successfully parsed as a general-numeric token by [`parse_general_numeric`].
+struct GeneralNumber<'a> {
+    negative: bool,
+    /// The power of ten of the most significant digit, plus the parsed exponent; `i64::MIN` when
+    /// the significand is all zeros, so zero, whether written `0` or `-0`, sorts below every
+    /// positive value and above every negative one.
+    magnitude: i64,
+    integer_part: &'a str,
+    fractional_part: &'a str,
+}
+
+impl<'a> GeneralNumber<'a> {
+    /// The field's digits, in order, with thousands separators, the decimal point, and leading
+    /// zeros removed.
+    fn significant_digits(&self) -> impl Iterator<Item = char> + 'a {
+        self.integer_part
+            .chars()
+            .filter(char::is_ascii_digit)
+            .chain(self.fractional_part.chars().filter(char::is_ascii_digit))
+            .skip_while(|c| *c == '0')
+    }
+}
+
+/// Compare two fields as general numbers, or return `None` if either isn't one.
+fn compare_general_numeric_fields(f1: &str, f2: &str) -> Option<Ordering> {
+    let n1 = parse_general_numeric(f1)?;
+    let n2 = parse_general_numeric(f2)?;
+
+    // Zero (magnitude `i64::MIN`) has no sign: "-0" and "0" are the same value, so a sign
+    // mismatch only matters when at least one side is actually nonzero.
+    let n1_negative = n1.negative && n1.magnitude != i64::MIN;
+    let n2_negative = n2.negative && n2.magnitude != i64::MIN;
+    if n1_negative != n2_negative {
+        return Some(if n1_negative { Less } else { Greater });
+    }
+    let sign_adjust = |ordering: Ordering| if n1_negative { ordering.reverse() } else { ordering };
+
+    match n1.magnitude.cmp(&n2.magnitude) {
+        Equal => {}
+        ordering => return Some(sign_adjust(ordering)),
+    }
+
+    let mut d1 = n1.significant_digits();
+    let mut d2 = n2.significant_digits();
+    loop {
+        match (d1.next(), d2.next()) {
+            (None, None) => return Some(Equal),
+            (Some(_), None) => return Some(sign_adjust(Greater)),
+            (None, Some(_)) => return Some(sign_adjust(Less)),
+            (Some(a), Some(b)) => match a.cmp(&b) {
+                Equal => continue,
+                ordering => return Some(sign_adjust(ordering)),
+            },
+        }
+    }
+}
+
+/// Parse `field` as a general-numeric token: an optional sign, digits with optional `,`
+/// thousands separators, an optional `.`-led fractional part, and an optional `e`/`E` exponent.
+/// Returns `None` if any part of `field` doesn't fit this grammar.
+fn parse_general_numeric(field: &str) -> Option<GeneralNumber<'_>> {
+    let mut chars = field.chars().peekable();
+    let mut pos = 0usize;
+
+    let negative = match chars.peek().copied() {
+        Some('-') => {
+            pos += chars.next().unwrap().len_utf8();
+            true
+        }
+        Some('+') => {
+            pos += chars.next().unwrap().len_utf8();
+            false
+        }
+        _ => false,
+    };
+
+    let int_start = pos;
+    let mut saw_digit = false;
+    while let Some(c) = chars.peek().copied() {
+        match c {
+            '0'..='9' => {
+                saw_digit = true;
+                pos += c.len_utf8();
+                chars.next();
+            }
+            ',' => {
+                pos += c.len_utf8();
+                chars.next();
+            }
+            _ => break,
+        }
+    }
+    let int_end = pos;
+    if !saw_digit {
+        return None;
+    }
+
+    let mut frac_start = int_end;
+    let mut frac_end = int_end;
+    if let Some('.') = chars.peek().copied() {
+        pos += chars.next().unwrap().len_utf8();
+        frac_start = pos;
+        let mut saw_frac_digit = false;
+        while let Some(c @ '0'..='9') = chars.peek().copied() {
+            saw_frac_digit = true;
+            pos += c.len_utf8();
+            chars.next();
+        }
+        frac_end = pos;
+        if !saw_frac_digit {
+            return None;
+        }
+    }
+
+    let mut exponent: i64 = 0;
+    if let Some('e' | 'E') = chars.peek().copied() {
+        pos += chars.next().unwrap().len_utf8();
+        let exponent_negative = match chars.peek().copied() {
+            Some('-') => {
+                pos += chars.next().unwrap().len_utf8();
+                true
+            }
+            Some('+') => {
+                pos += chars.next().unwrap().len_utf8();
+                false
+            }
+            _ => false,
+        };
+        let mut saw_exponent_digit = false;
+        let mut exponent_value: i64 = 0;
+        while let Some(c) = chars.peek().copied() {
+            match c.to_digit(10) {
+                Some(digit) => {
+                    saw_exponent_digit = true;
+                    exponent_value = exponent_value.saturating_mul(10).saturating_add(i64::from(digit));
+                    pos += c.len_utf8();
+                    chars.next();
+                }
+                None => break,
+            }
+        }
+        if !saw_exponent_digit {
+            return None;
+        }
+        exponent = if exponent_negative { -exponent_value } else { exponent_value };
+    }
+
+    if chars.next().is_some() {
+        return None;  // trailing characters after the number, so this field isn't purely numeric
+    }
+
+    let integer_part = &field[int_start..int_end];
+    let fractional_part = &field[frac_start..frac_end];
+    let magnitude = general_numeric_magnitude(integer_part, fractional_part)
+        .map(|place| place.saturating_add(exponent))
+        .unwrap_or(i64::MIN);
+
+    Some(GeneralNumber { negative, magnitude, integer_part, fractional_part })
+}
+
+/// The power of ten of the most significant digit across `integer_part` followed by
+/// `fractional_part`, or `None` if both parts are entirely zero (or empty).
+fn general_numeric_magnitude(integer_part: &str, fractional_part: &str) -> Option<i64> {
+    let int_digits = integer_part.chars().filter(char::is_ascii_digit);
+    let int_len = int_digits.clone().count() as i64;
+    let int_leading_zeros = int_digits.take_while(|c| *c == '0').count() as i64;
+    if int_leading_zeros < int_len {
+        return Some(int_len - int_leading_zeros - 1);
+    }
+
+    let frac_leading_zeros = fractional_part.chars().take_while(|c| *c == '0').count() as i64;
+    let frac_len = fractional_part.chars().filter(char::is_ascii_digit).count() as i64;
+    if frac_leading_zeros < frac_len {
+        return Some(-(frac_leading_zeros + 1));
+    }
+
+    None
+}
+
+/// The literal PostgreSQL COPY representation of SQL `NULL`.
+const PG_NULL: &str = "\\N";
+
+/// NULL-aware variant of [`tsv_cmp`].
+///
+/// `tsv_cmp` has no notion of PostgreSQL's `\N` COPY representation of SQL `NULL` and orders it
+/// as an ordinary string. `tsv_cmp_nulls` instead detects a `\N` field at each field boundary,
+/// before the negative-sign/leading-zero logic in `tsv_cmp` ever runs, and treats it as a
+/// sentinel that sorts before every real value when `nulls_first` is `true`, or after every real
+/// value when it's `false` -- matching how databases order `NULL`s. Fields that aren't `\N` on
+/// either side are compared with `tsv_cmp`, unchanged.
+///
+/// # Examples
+///
+/// ```
+/// use pgtricks::tsv_cmp_nulls;
+/// use std::cmp::Ordering;
+///
+/// assert_eq!(tsv_cmp_nulls("\\N", "0", true), Ordering::Less);
+/// assert_eq!(tsv_cmp_nulls("\\N", "0", false), Ordering::Greater);
+/// ```
+pub fn tsv_cmp_nulls(l1: &str, l2: &str, nulls_first: bool) -> Ordering {
+    tsv_cmp_nulls_with(l1, l2, nulls_first, tsv_cmp)
+}
+
+/// Like [`tsv_cmp_nulls`], but comparing non-`NULL` fields with `cmp` instead of always `tsv_cmp`,
+/// so NULL-awareness can be layered on top of [`tsv_cmp_natural`] or [`tsv_cmp_general`] too.
+fn tsv_cmp_nulls_with(
+    l1: &str,
+    l2: &str,
+    nulls_first: bool,
+    cmp: fn(&str, &str) -> Ordering,
+) -> Ordering {
+    let mut l1_fields = l1.split('\t');
+    let mut l2_fields = l2.split('\t');
+    loop {
+        match (l1_fields.next(), l2_fields.next()) {
+            (None, None) => return Equal,
+            (Some(_), None) => return Greater,
+            (None, Some(_)) => return Less,
+            (Some(f1), Some(f2)) => match (f1 == PG_NULL, f2 == PG_NULL) {
+                (true, true) => continue,
+                (true, false) => return if nulls_first { Less } else { Greater },
+                (false, true) => return if nulls_first { Greater } else { Less },
+                (false, false) => match cmp(f1, f2) {
+                    Equal => continue,
+                    ord => return ord,
+                },
+            },
+        }
+    }
+}
+
+/// [`tsv_cmp_nulls`] with `nulls_first` fixed to `true`, for use where a plain
+/// `fn(&str, &str) -> Ordering` is required, e.g. [`select_comparator`].
+fn tsv_cmp_nulls_first(l1: &str, l2: &str) -> Ordering {
+    tsv_cmp_nulls_with(l1, l2, true, tsv_cmp)
+}
+
+/// [`tsv_cmp_nulls`] with `nulls_first` fixed to `false`.
+fn tsv_cmp_nulls_last(l1: &str, l2: &str) -> Ordering {
+    tsv_cmp_nulls_with(l1, l2, false, tsv_cmp)
+}
+
+/// [`tsv_cmp_natural`], with `\N` NULL fields sorted before any real value.
+fn tsv_cmp_natural_nulls_first(l1: &str, l2: &str) -> Ordering {
+    tsv_cmp_nulls_with(l1, l2, true, tsv_cmp_natural)
+}
+
+/// [`tsv_cmp_natural`], with `\N` NULL fields sorted after any real value.
+fn tsv_cmp_natural_nulls_last(l1: &str, l2: &str) -> Ordering {
+    tsv_cmp_nulls_with(l1, l2, false, tsv_cmp_natural)
+}
+
+/// [`tsv_cmp_general`], with `\N` NULL fields sorted before any real value.
+fn tsv_cmp_general_nulls_first(l1: &str, l2: &str) -> Ordering {
+    tsv_cmp_nulls_with(l1, l2, true, tsv_cmp_general)
+}
+
+/// [`tsv_cmp_general`], with `\N` NULL fields sorted after any real value.
+fn tsv_cmp_general_nulls_last(l1: &str, l2: &str) -> Ordering {
+    tsv_cmp_nulls_with(l1, l2, false, tsv_cmp_general)
+}
+
 #[cfg(test)]
 #[macro_use]
 extern crate rstest;
@@ -389,4 +1209,232 @@ mod tests {
             expected as i8,
         );
     }
+
+    #[rstest]
+    // embedded digit runs, not just a leading one
+    #[case("img2", "img10", Less)]
+    #[case("img10", "img2", Greater)]
+    #[case("img2", "img2", Equal)]
+    #[case("file2.txt", "file10.txt", Less)]
+    #[case("file10.txt", "file2.txt", Greater)]
+    #[case("v1.9", "v1.10", Less)]
+    #[case("v1.10", "v1.9", Greater)]
+    // leading zeros are a stable tiebreak, not ignored entirely
+    #[case("01", "1", Greater)]
+    #[case("1", "01", Less)]
+    #[case("01", "01", Equal)]
+    #[case("00", "0", Greater)]
+    // still numeric at the start of a field
+    #[case("123", "124", Less)]
+    #[case("124", "123", Greater)]
+    #[case("-123", "123", Less)]
+    #[case("123", "-123", Greater)]
+    // plain lexicographic comparison where there are no digits
+    #[case("our", "own", Less)]
+    #[case("own", "our", Greater)]
+    // multiple fields
+    #[case("img2\tfoo", "img10\tfoo", Less)]
+    #[case("identical\timg2", "identical\timg10", Less)]
+    #[case("identical\tlines\n", "identical\tlines\n", Equal)]
+    fn test_linecomp_natural(#[case] l1: &str, #[case] l2: &str, #[case] expected: Ordering) {
+        assert_eq!(
+            tsv_cmp_natural(l1, l2),
+            expected,
+            "tsv_cmp_natural({}, {}) == {}, expected {}",
+            l1,
+            l2,
+            tsv_cmp_natural(l1, l2) as i8,
+            expected as i8,
+        );
+    }
+
+    #[rstest]
+    // negative-prefix branch: decisive span is just the sign
+    #[case("-123", "123", Less, 0..1, 0..1)]
+    #[case("123", "-123", Greater, 0..1, 0..1)]
+    // leading-zero-skip branch: zeros are consumed but don't affect the outcome
+    #[case("007", "7", Equal, 0..3, 0..1)]
+    // integer-length branch: decided as soon as the shorter field runs out of digits
+    #[case("12", "123", Less, 0..2, 0..3)]
+    #[case("123", "12", Greater, 0..3, 0..2)]
+    // fractional branch: decided at the first differing digit after the decimal point
+    #[case("123.0", "123.1", Less, 0..5, 0..5)]
+    #[case("123.1", "123.0", Greater, 0..5, 0..5)]
+    fn test_linecomp_debug(
+        #[case] l1: &str,
+        #[case] l2: &str,
+        #[case] expected_ordering: Ordering,
+        #[case] expected_l1_range: Range<usize>,
+        #[case] expected_l2_range: Range<usize>,
+    ) {
+        let (ordering, l1_range, l2_range) = tsv_cmp_debug(l1, l2);
+        assert_eq!(ordering, expected_ordering, "tsv_cmp_debug({}, {}) ordering", l1, l2);
+        assert_eq!(l1_range, expected_l1_range, "tsv_cmp_debug({}, {}) l1 range", l1, l2);
+        assert_eq!(l2_range, expected_l2_range, "tsv_cmp_debug({}, {}) l2 range", l1, l2);
+    }
+
+    #[rstest]
+    // whole-line comparison (empty key_fields)
+    #[case("1\tfoo", "1\tfoo", &[], true)]
+    #[case("1\tfoo", "1\tbar", &[], false)]
+    // only the named columns need to match
+    #[case("1\tfoo", "1\tbar", &[0], true)]
+    #[case("1\tfoo", "2\tfoo", &[0], false)]
+    #[case("1\tfoo\tbar", "9\tfoo\tbar", &[1, 2], true)]
+    fn test_rows_are_duplicates(
+        #[case] prev: &str,
+        #[case] current: &str,
+        #[case] key_fields: &[usize],
+        #[case] expected: bool,
+    ) {
+        assert_eq!(rows_are_duplicates(prev, current, key_fields, tsv_cmp), expected);
+    }
+
+    #[rstest]
+    // "007" and "7" are tsv_cmp-Equal (leading zeros are stripped) but natural-cmp-unequal
+    // (natural tie-breaks same-value digit runs by their leading-zero count), so dedup must use
+    // whichever comparator the sort used rather than always falling back to `tsv_cmp`
+    #[case("007", "7", tsv_cmp, true)]
+    #[case("007", "7", tsv_cmp_natural, false)]
+    fn test_rows_are_duplicates_uses_given_comparator(
+        #[case] prev: &str,
+        #[case] current: &str,
+        #[case] cmp: fn(&str, &str) -> Ordering,
+        #[case] expected: bool,
+    ) {
+        assert_eq!(rows_are_duplicates(prev, current, &[], cmp), expected);
+    }
+
+    #[rstest]
+    #[case(vec!["1\tfoo".to_string(), "1\tfoo".to_string(), "2\tbar".to_string()], vec![], false, false, None, (vec!["1\tfoo".to_string(), "2\tbar".to_string()], 1))]
+    #[case(vec!["1\tfoo".to_string(), "1\tbar".to_string(), "2\tbar".to_string()], vec![0], false, false, None, (vec!["1\tfoo".to_string(), "2\tbar".to_string()], 1))]
+    #[case(vec!["1\tfoo".to_string()], vec![], false, false, None, (vec!["1\tfoo".to_string()], 0))]
+    // under natural order "007" and "7" are adjacent-but-distinct, unlike under plain tsv_cmp
+    #[case(vec!["007".to_string(), "7".to_string()], vec![], false, false, None, (vec!["007".to_string()], 1))]
+    #[case(vec!["007".to_string(), "7".to_string()], vec![], true, false, None, (vec!["007".to_string(), "7".to_string()], 0))]
+    fn test_dedup_lines(
+        #[case] lines: Vec<String>,
+        #[case] key_fields: Vec<usize>,
+        #[case] natural: bool,
+        #[case] general_numeric: bool,
+        #[case] nulls_first: Option<bool>,
+        #[case] expected: (Vec<String>, usize),
+    ) {
+        assert_eq!(dedup_lines(lines, key_fields, natural, general_numeric, nulls_first), expected);
+    }
+
+    #[rstest]
+    #[case(false, false, None, "123", "123", Equal)] // plain tsv_cmp
+    #[case(true, false, None, "007", "7", Greater)] // tsv_cmp_natural
+    #[case(false, true, None, "0", "0.5", Less)] // tsv_cmp_general
+    #[case(true, true, None, "1.5e2", "2e1", Greater)] // general_numeric takes precedence over natural
+    #[case(false, false, Some(true), "\\N", "0", Less)] // nulls_first, plain base
+    #[case(false, false, Some(false), "\\N", "0", Greater)] // nulls_last, plain base
+    #[case(true, false, Some(true), "\\N", "img2", Less)] // nulls_first, natural base
+    #[case(false, true, Some(true), "\\N", "0.5", Less)] // nulls_first, general_numeric base
+    fn test_select_comparator(
+        #[case] natural: bool,
+        #[case] general_numeric: bool,
+        #[case] nulls_first: Option<bool>,
+        #[case] l1: &str,
+        #[case] l2: &str,
+        #[case] expected: Ordering,
+    ) {
+        assert_eq!(select_comparator(natural, general_numeric, nulls_first)(l1, l2), expected);
+    }
+
+    #[rstest]
+    // scientific notation
+    #[case("1.5e2", "2e1", Greater)]  // 150 > 20
+    #[case("2e1", "1.5e2", Less)]
+    // known imprecision: equal-magnitude values with different trailing zeros in their
+    // significand compare unequal ("15" vs "150" digit streams), even though 1.5e2 == 150 --
+    // the same class as tsv_cmp's documented "123.00 > 123.0"
+    #[case("1.5e2", "150", Less)]
+    #[case("150", "1.5e2", Greater)]
+    // explicit plus signs don't affect the value
+    #[case("+5", "5", Equal)]
+    #[case("+5", "+5", Equal)]
+    // thousands separators are stripped before comparing
+    #[case("1,000,000", "999999", Greater)]
+    #[case("999999", "1,000,000", Less)]
+    // purely-fractional values are ordered by their leading zeros
+    #[case("0.001", "0.0001", Greater)]
+    #[case("0.0001", "0.001", Less)]
+    #[case("0.001", "0.001", Equal)]
+    // negative general-numeric values
+    #[case("-1.5e2", "1.5e2", Less)]
+    #[case("-1.5e2", "-2e1", Less)]  // -150 < -20
+    // zero sorts below any positive value, including sub-1 fractions, and above any negative one
+    #[case("0", "0.5", Less)]
+    #[case("0.5", "0", Greater)]
+    #[case("0", "0.0001", Less)]
+    #[case("-0.5", "-0", Less)]
+    #[case("0", "0", Equal)]
+    #[case("0.0", "0", Equal)]
+    #[case("0", "-0", Equal)]
+    #[case("-0", "0", Equal)]
+    // pathologically large exponents saturate instead of overflowing
+    #[case("1e99999999999999999999", "1e99999999999999999998", Equal)]
+    #[case("1e99999999999999999999", "1", Greater)]
+    // multiple fields
+    #[case("identical\t1e3", "identical\t999", Greater)]
+    fn test_linecomp_general(#[case] l1: &str, #[case] l2: &str, #[case] expected: Ordering) {
+        assert_eq!(tsv_cmp_general(l1, l2), expected, "tsv_cmp_general({}, {})", l1, l2);
+    }
+
+    #[rstest]
+    // fields that aren't clean general-numeric tokens fall back to plain tsv_cmp
+    #[case("2023-01-01", "2023-01-02")]
+    #[case("our", "own")]
+    #[case("123abc", "123abd")]
+    fn test_linecomp_general_fallback(#[case] l1: &str, #[case] l2: &str) {
+        assert_eq!(tsv_cmp_general(l1, l2), tsv_cmp(l1, l2));
+    }
+
+    #[rstest]
+    #[case("\\N", "0", true, Less)]
+    #[case("\\N", "0", false, Greater)]
+    #[case("0", "\\N", true, Greater)]
+    #[case("0", "\\N", false, Less)]
+    #[case("\\N", "\\N", true, Equal)]
+    #[case("\\N", "\\N", false, Equal)]
+    #[case("1", "2", true, Less)]
+    #[case("1", "2", false, Less)]
+    #[case("a\t\\N", "a\t0", true, Less)]
+    #[case("a\t\\N", "a\t0", false, Greater)]
+    #[case("x\\Ny", "\\N", true, Greater)]
+    // a value-equal-but-string-unequal field (tsv_cmp("00", "0") == Equal) must not short-circuit
+    // the comparison; later fields still decide the outcome
+    #[case("00\tb", "0\ta", true, Greater)]
+    #[case("01\tz", "1\ta", true, Greater)]
+    fn test_linecomp_nulls(
+        #[case] l1: &str,
+        #[case] l2: &str,
+        #[case] nulls_first: bool,
+        #[case] expected: Ordering,
+    ) {
+        assert_eq!(tsv_cmp_nulls(l1, l2, nulls_first), expected);
+    }
+
+    #[rstest]
+    // natural-order base: non-NULL fields still sort by embedded digit runs
+    #[case(tsv_cmp_natural_nulls_first, "\\N", "img2", Less)]
+    #[case(tsv_cmp_natural_nulls_last, "\\N", "img2", Greater)]
+    #[case(tsv_cmp_natural_nulls_first, "img2", "img10", Less)]
+    // general-numeric base: non-NULL fields still sort as real numbers
+    #[case(tsv_cmp_general_nulls_first, "\\N", "0.5", Less)]
+    #[case(tsv_cmp_general_nulls_last, "\\N", "0.5", Greater)]
+    #[case(tsv_cmp_general_nulls_first, "1.5e2", "2e1", Greater)]
+    // a value-equal-but-string-unequal field (tsv_cmp_general("+5", "5") == Equal) must not
+    // short-circuit the comparison either
+    #[case(tsv_cmp_general_nulls_first, "+5\tb", "5\ta", Greater)]
+    fn test_linecomp_nulls_with_base_comparator(
+        #[case] cmp: fn(&str, &str) -> Ordering,
+        #[case] l1: &str,
+        #[case] l2: &str,
+        #[case] expected: Ordering,
+    ) {
+        assert_eq!(cmp(l1, l2), expected);
+    }
 }